@@ -1,4 +1,7 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Read;
 use std::str::FromStr;
 
 #[derive(Debug)]
@@ -14,28 +17,394 @@ pub enum JSONValue {
 
 }
 
+fn escape_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\u{0008}' => out.push_str("\\b"),
+            '\u{000C}' => out.push_str("\\f"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn format_decimal(n: f64) -> String {
+    if !n.is_finite() {
+        return "null".to_string();
+    }
+    let s = format!("{}", n);
+    if s.contains('.') || s.contains('e') || s.contains('E') {
+        s
+    } else {
+        format!("{}.0", s)
+    }
+}
+
+/// Renders compact JSON text.
+///
+/// JSON numbers have no representation for NaN or infinity, so a
+/// `JSONValue::Decimal` holding one of those (only possible if it was
+/// constructed directly rather than parsed — `parse_num` rejects them)
+/// serializes to the literal `null`. That value will then parse back as
+/// `JSONValue::Null` rather than the original `Decimal`; values produced by
+/// this crate's own parser are always finite and do not hit this case.
+impl fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JSONValue::Integer(n) => write!(f, "{}", n),
+            JSONValue::Decimal(n) => write!(f, "{}", format_decimal(*n)),
+            JSONValue::Boolean(b) => write!(f, "{}", b),
+            JSONValue::Null => write!(f, "null"),
+            JSONValue::String(s) => write!(f, "{}", escape_string(s)),
+            JSONValue::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JSONValue::Map(map) => {
+                write!(f, "{{")?;
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                for (i, key) in keys.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", escape_string(key), map[*key])?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl JSONValue {
+
+    /// Renders indented, multi-line JSON text. See the `Display` impl for
+    /// the caveat around non-finite `Decimal` values.
+    pub fn to_string_pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, indent, 0);
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize, depth: usize) {
+        match self {
+            JSONValue::List(items) => {
+                if items.is_empty() {
+                    out.push_str("[]");
+                    return;
+                }
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&" ".repeat(indent * (depth + 1)));
+                    item.write_pretty(out, indent, depth + 1);
+                    if i + 1 < items.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+                out.push_str(&" ".repeat(indent * depth));
+                out.push(']');
+            }
+            JSONValue::Map(map) => {
+                if map.is_empty() {
+                    out.push_str("{}");
+                    return;
+                }
+                out.push_str("{\n");
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                for (i, key) in keys.iter().enumerate() {
+                    out.push_str(&" ".repeat(indent * (depth + 1)));
+                    out.push_str(&escape_string(key));
+                    out.push_str(": ");
+                    map[*key].write_pretty(out, indent, depth + 1);
+                    if i + 1 < keys.len() {
+                        out.push(',');
+                    }
+                    out.push('\n');
+                }
+                out.push_str(&" ".repeat(indent * depth));
+                out.push('}');
+            }
+            _ => out.push_str(&self.to_string()),
+        }
+    }
+
+}
+
+fn type_name(value: &JSONValue) -> &'static str {
+    match value {
+        JSONValue::Integer(_) => "integer",
+        JSONValue::Decimal(_) => "decimal",
+        JSONValue::List(_) => "list",
+        JSONValue::Map(_) => "map",
+        JSONValue::Boolean(_) => "boolean",
+        JSONValue::String(_) => "string",
+        JSONValue::Null => "null",
+    }
+}
+
+/// Returned by the `TryFrom<JSONValue>` conversions when the value is not
+/// of the expected type.
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub expected: &'static str,
+    pub found: &'static str,
+}
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)
+    }
+}
+
+impl JSONValue {
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JSONValue::Integer(n) => Some(*n),
+            _ => None
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JSONValue::Integer(n) => Some(*n as f64),
+            JSONValue::Decimal(n) => Some(*n),
+            _ => None
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JSONValue::Boolean(b) => Some(*b),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JSONValue::String(s) => Some(s.as_str()),
+            _ => None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JSONValue>> {
+        match self {
+            JSONValue::List(l) => Some(l),
+            _ => None
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&HashMap<String, JSONValue>> {
+        match self {
+            JSONValue::Map(m) => Some(m),
+            _ => None
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&JSONValue> {
+        self.as_object().and_then(|m| m.get(key))
+    }
+
+    pub fn get_index(&self, index: usize) -> Option<&JSONValue> {
+        self.as_array().and_then(|l| l.get(index))
+    }
+
+}
+
+impl TryFrom<JSONValue> for i64 {
+    type Error = TypeMismatch;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        match value {
+            JSONValue::Integer(n) => Ok(n),
+            other => Err(TypeMismatch {expected: "integer", found: type_name(&other)})
+        }
+    }
+
+}
+
+impl TryFrom<JSONValue> for f64 {
+    type Error = TypeMismatch;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        match value {
+            JSONValue::Integer(n) => Ok(n as f64),
+            JSONValue::Decimal(n) => Ok(n),
+            other => Err(TypeMismatch {expected: "number", found: type_name(&other)})
+        }
+    }
+
+}
+
+impl TryFrom<JSONValue> for bool {
+    type Error = TypeMismatch;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        match value {
+            JSONValue::Boolean(b) => Ok(b),
+            other => Err(TypeMismatch {expected: "boolean", found: type_name(&other)})
+        }
+    }
+
+}
+
+impl TryFrom<JSONValue> for String {
+    type Error = TypeMismatch;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        match value {
+            JSONValue::String(s) => Ok(s),
+            other => Err(TypeMismatch {expected: "string", found: type_name(&other)})
+        }
+    }
+
+}
+
+impl TryFrom<JSONValue> for Vec<JSONValue> {
+    type Error = TypeMismatch;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        match value {
+            JSONValue::List(l) => Ok(l),
+            other => Err(TypeMismatch {expected: "list", found: type_name(&other)})
+        }
+    }
+
+}
+
+impl TryFrom<JSONValue> for HashMap<String, JSONValue> {
+    type Error = TypeMismatch;
+
+    fn try_from(value: JSONValue) -> Result<Self, Self::Error> {
+        match value {
+            JSONValue::Map(m) => Ok(m),
+            other => Err(TypeMismatch {expected: "map", found: type_name(&other)})
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+
+    UnexpectedEnd,
+    InvalidInput,
+    KeyMustBeString,
+    ExpectedColon,
+    ExpectedCommaOrBracket,
+    ExpectedCommaOrBrace,
+    TrailingCharacters,
+    InvalidEscape,
+    InvalidNumber,
+    UnclosedString,
+    UnclosedList,
+    UnclosedMap,
+
+}
+
 #[derive(Debug)]
 pub struct ParseJSONError {
 
-    error: String,
+    code: ErrorCode,
+    offset: usize,
+    line: usize,
+    column: usize,
 
 }
 
 impl ParseJSONError {
 
-    fn new(message: impl Into<String>) -> ParseJSONError {
-        ParseJSONError {error: message.into()}
+    fn new(code: ErrorCode, offset: usize) -> ParseJSONError {
+        ParseJSONError {code, offset, line: 1, column: 1}
+    }
+
+    fn offset_by(mut self, amount: usize) -> ParseJSONError {
+        self.offset += amount;
+        self
+    }
+
+    fn locate(mut self, input: &[char]) -> ParseJSONError {
+        let mut line = 1;
+        let mut column = 1;
+        for &c in input.iter().take(self.offset) {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        self.line = line;
+        self.column = column;
+        self
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
     }
 
 }
 
+/// Parses a single JSON value from the start of `input`, ignoring anything
+/// that follows it. Returns the value along with the number of `char`s
+/// consumed, so callers can keep parsing further values out of the same
+/// buffer.
+pub fn parse_prefix(input: &str) -> Result<(JSONValue, usize), ParseJSONError> {
+    let chars: Vec<char> = input.chars().collect();
+    parse_json_trim(&chars).map_err(|e| e.locate(&chars))
+}
+
+/// Parses `input` as a single JSON value, requiring that only whitespace
+/// follows it. This is the behavior used by `FromStr`.
+pub fn parse_strict(input: &str) -> Result<JSONValue, ParseJSONError> {
+    let chars: Vec<char> = input.chars().collect();
+    parse_strict_chars(&chars).map_err(|e| e.locate(&chars))
+}
+
+fn parse_strict_chars(chars: &[char]) -> Result<JSONValue, ParseJSONError> {
+    let (value, consumed) = parse_json_trim(chars)?;
+    let (_, trimmed) = trim_start(&chars[consumed..]);
+    if consumed + trimmed == chars.len() {
+        Ok(value)
+    } else {
+        Err(ParseJSONError::new(ErrorCode::TrailingCharacters, consumed + trimmed))
+    }
+}
+
 impl FromStr for JSONValue {
     type Err = ParseJSONError;
 
     fn from_str(input: &str) -> Result<JSONValue, ParseJSONError> {
-        let input = input.trim_start();
-        let chars: Vec<char> = input.chars().collect();
-        Ok(parse_json_trim(&chars)?.0)
+        parse_strict(input)
     }
 
 }
@@ -49,15 +418,15 @@ fn trim_start(input: &[char]) -> (&[char], usize) {
 }
 
 fn parse_json_trim(input: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
-    let (input, trimmed) = trim_start(input);
-    let mut result = parse_json(input)?;
+    let (trimmed_input, trimmed) = trim_start(input);
+    let mut result = parse_json(trimmed_input).map_err(|e| e.offset_by(trimmed))?;
     result.1 += trimmed;
     Ok(result)
 }
 
 fn parse_json(input: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
     if input.len() == 0 {
-        return Err(ParseJSONError::new("Empty input"));
+        return Err(ParseJSONError::new(ErrorCode::UnexpectedEnd, 0));
     }
     return match input[0] {
         't' => Ok((JSONValue::Boolean(true), 4)),
@@ -66,30 +435,69 @@ fn parse_json(input: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
         '"' => parse_string(input),
         '[' => parse_list(input),
         '{' => parse_map(input),
-        '0'..='9' | '.' => parse_num(input),
-        _ => Err(ParseJSONError::new("Invalid input"))
+        '0'..='9' | '-' => parse_num(input),
+        _ => Err(ParseJSONError::new(ErrorCode::InvalidInput, 0))
     }
 }
 
+fn parse_hex4(chars: &[char], start: usize) -> Result<u16, ParseJSONError> {
+    if start + 4 > chars.len() {
+        return Err(ParseJSONError::new(ErrorCode::InvalidEscape, chars.len()));
+    }
+    let digits: String = chars[start..start + 4].iter().collect();
+    u16::from_str_radix(&digits, 16).map_err(|_| ParseJSONError::new(ErrorCode::InvalidEscape, start))
+}
+
 fn parse_string(chars: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
+    let inner = &chars[1..];
+    let (value, length) = parse_string_body(inner).map_err(|e| e.offset_by(1))?;
+    Ok((value, length))
+}
+
+fn parse_string_body(chars: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
     let mut out = String::new();
-    let chars = &chars[1..];
     let mut i = 0;
     while i < chars.len() {
         let c = chars[i];
         match c {
             '\\' => {
                 if i == chars.len() - 1 {
-                    return Err(ParseJSONError::new(""));
+                    return Err(ParseJSONError::new(ErrorCode::UnclosedString, i));
                 }
                 let next = chars[i + 1];
-                out.push(match next {
-                    'n' => '\n',
-                    't' => '\t',
-                    'r' => '\r',
-                    _ => next
-                });
-                i += 1;
+                match next {
+                    'n' => { out.push('\n'); i += 1; }
+                    't' => { out.push('\t'); i += 1; }
+                    'r' => { out.push('\r'); i += 1; }
+                    'b' => { out.push('\u{0008}'); i += 1; }
+                    'f' => { out.push('\u{000C}'); i += 1; }
+                    '/' => { out.push('/'); i += 1; }
+                    'u' => {
+                        let hi = parse_hex4(chars, i + 2)?;
+                        if (0xDC00..=0xDFFF).contains(&hi) {
+                            return Err(ParseJSONError::new(ErrorCode::InvalidEscape, i));
+                        } else if (0xD800..=0xDBFF).contains(&hi) {
+                            if i + 8 > chars.len() || chars[i + 6] != '\\' || chars[i + 7] != 'u' {
+                                return Err(ParseJSONError::new(ErrorCode::InvalidEscape, i));
+                            }
+                            let lo = parse_hex4(chars, i + 8)?;
+                            if !(0xDC00..=0xDFFF).contains(&lo) {
+                                return Err(ParseJSONError::new(ErrorCode::InvalidEscape, i));
+                            }
+                            let combined = (((hi - 0xD800) as u32) << 10) + ((lo - 0xDC00) as u32) + 0x10000;
+                            let combined = char::from_u32(combined)
+                                .ok_or_else(|| ParseJSONError::new(ErrorCode::InvalidEscape, i))?;
+                            out.push(combined);
+                            i += 11;
+                        } else {
+                            let single = char::from_u32(hi as u32)
+                                .ok_or_else(|| ParseJSONError::new(ErrorCode::InvalidEscape, i))?;
+                            out.push(single);
+                            i += 5;
+                        }
+                    }
+                    _ => { out.push(next); i += 1; }
+                }
             }
             '"' => {
                 return Ok((JSONValue::String(out), i + 2));
@@ -98,146 +506,409 @@ fn parse_string(chars: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
         }
         i += 1;
     }
-    Err(ParseJSONError::new("Unclosed string"))
+    Err(ParseJSONError::new(ErrorCode::UnclosedString, i))
 }
 
 fn parse_list(chars: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
-    let mut chars = &chars[1..];
+    let mut rest = &chars[1..];
+    let mut length = 1usize;
     let mut list: Vec<JSONValue> = vec![];
-    let mut length = 1;
-    if chars[0] == ']' {
-        return Ok((JSONValue::List(list), length));
+    if rest.is_empty() {
+        return Err(ParseJSONError::new(ErrorCode::UnclosedList, length));
+    }
+    if rest[0] == ']' {
+        return Ok((JSONValue::List(list), length + 1));
     }
     loop {
-        if chars.len() == 0 {
-            return Err(ParseJSONError::new("Unclosed list"));
-        }
-        let next = parse_json_trim(chars)?;
-        length += next.1;
-        chars = &chars[next.1..];
-        list.push(next.0);
-        let (newchars, trimmed) = trim_start(chars);
-        chars = newchars;
-        length += trimmed + 1;
-        if chars.len() == 0 {
-            return Err(ParseJSONError::new("Unclosed list"));
-        }
-        match chars[0] {
+        let (value, consumed) = parse_json_trim(rest).map_err(|e| e.offset_by(length))?;
+        length += consumed;
+        rest = &rest[consumed..];
+        list.push(value);
+        let (trimmed_rest, trimmed) = trim_start(rest);
+        rest = trimmed_rest;
+        length += trimmed;
+        if rest.is_empty() {
+            return Err(ParseJSONError::new(ErrorCode::UnclosedList, length));
+        }
+        match rest[0] {
             ',' => {
-                chars = &chars[1..];
+                rest = &rest[1..];
+                length += 1;
             }
             ']' => {
-                return Ok((JSONValue::List(list), length))
+                return Ok((JSONValue::List(list), length + 1))
             }
             _ => {
-                return Err(ParseJSONError::new("Improperly delimited list"));
+                return Err(ParseJSONError::new(ErrorCode::ExpectedCommaOrBracket, length));
             }
         }
     }
 }
 
 fn parse_map(chars: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
-    let mut chars = &chars[1..];
+    let mut rest = &chars[1..];
+    let mut length = 1usize;
     let mut map: HashMap<String, JSONValue> = Default::default();
-    let mut length = 1;
-    if chars[0] == '}' {
-        return Ok((JSONValue::Map(map), length));
+    if rest.is_empty() {
+        return Err(ParseJSONError::new(ErrorCode::UnclosedMap, length));
+    }
+    if rest[0] == '}' {
+        return Ok((JSONValue::Map(map), length + 1));
     }
     loop {
-        if chars.len() == 0 {
-            return Err(ParseJSONError::new("Unclosed map"));
-        }
-        let (key, value, parsed) = parse_map_entry(chars)?;
+        let (key, value, parsed) = parse_map_entry(rest).map_err(|e| e.offset_by(length))?;
         length += parsed;
-        chars = &chars[parsed..];
+        rest = &rest[parsed..];
         map.insert(key, value);
-        let (newchars, trimmed) = trim_start(chars);
-        chars = newchars;
-        length += trimmed + 1;
-        if chars.len() == 0 {
-            return Err(ParseJSONError::new("Unclosed list"));
+        let (trimmed_rest, trimmed) = trim_start(rest);
+        rest = trimmed_rest;
+        length += trimmed;
+        if rest.is_empty() {
+            return Err(ParseJSONError::new(ErrorCode::UnclosedMap, length));
         }
-        match chars[0] {
+        match rest[0] {
             ',' => {
-                chars = &chars[1..];
+                rest = &rest[1..];
+                length += 1;
             }
             '}' => {
-                return Ok((JSONValue::Map(map), length))
+                return Ok((JSONValue::Map(map), length + 1))
             }
             _ => {
-                return Err(ParseJSONError::new("Improperly delimited list"));
+                return Err(ParseJSONError::new(ErrorCode::ExpectedCommaOrBrace, length));
             }
         }
     }
 }
 
 fn parse_map_entry(chars: &[char]) -> Result<(String, JSONValue, usize), ParseJSONError> {
+    let (_, leading) = trim_start(chars);
     let (key, mut length) = parse_json_trim(chars)?;
     let key = match key {
         JSONValue::String(s) => s,
-        _ => return Err(ParseJSONError::new("Map key is not string"))
+        _ => return Err(ParseJSONError::new(ErrorCode::KeyMustBeString, leading))
     };
-    let chars = &chars[length..];
-    let (mut chars, trimmed) = trim_start(chars);
+    let rest = &chars[length..];
+    let (rest, trimmed) = trim_start(rest);
     length += trimmed;
-    if chars.len() == 0 || chars[0] != ':' {
-        return Err(ParseJSONError::new("Improperly delimited map entry"));
+    if rest.is_empty() || rest[0] != ':' {
+        return Err(ParseJSONError::new(ErrorCode::ExpectedColon, length));
     }
     length += 1;
-    chars = &chars[1..];
-    let (value, value_length) = parse_json_trim(chars)?;
+    let rest = &rest[1..];
+    let (value, value_length) = parse_json_trim(rest).map_err(|e| e.offset_by(length))?;
     length += value_length;
     Ok((key, value, length))
 }
 
 fn parse_num(chars: &[char]) -> Result<(JSONValue, usize), ParseJSONError> {
-    let mut num = 0i64;
-    let negative = chars[0] == '-';
-    let mut decimal_index = -1;
-    let mut decimal = 0;
-    let mut length = 0usize;
-    let mut iter = chars.iter();
-    if negative {
-        iter.next();
-        length += 1;
-    }
-    for c in iter {
-        match c {
-            '0'..='9' => {
-                let to_inc = if decimal_index == -1 {&mut num} else {&mut decimal};
-                *to_inc *= 10;
-                *to_inc += (*c as i64) - ('0' as i64);
-            }
-            '.' => {
-                if decimal_index != -1 {
-                    return Err(ParseJSONError::new("Extra decimal point in number"));
-                }
-                decimal_index = length as i32;
+    let mut i = 0;
+    if chars.first() == Some(&'-') {
+        i += 1;
+    }
+    if i >= chars.len() || !chars[i].is_ascii_digit() {
+        return Err(ParseJSONError::new(ErrorCode::InvalidNumber, i));
+    }
+    if chars[i] == '0' {
+        i += 1;
+        if i < chars.len() && chars[i].is_ascii_digit() {
+            return Err(ParseJSONError::new(ErrorCode::InvalidNumber, i));
+        }
+    } else {
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    let mut is_float = false;
+    if i < chars.len() && chars[i] == '.' {
+        let frac_start = i + 1;
+        let mut j = frac_start;
+        while j < chars.len() && chars[j].is_ascii_digit() {
+            j += 1;
+        }
+        if j == frac_start {
+            return Err(ParseJSONError::new(ErrorCode::InvalidNumber, j));
+        }
+        is_float = true;
+        i = j;
+    }
+    if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+        let mut j = i + 1;
+        if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+            j += 1;
+        }
+        let exp_start = j;
+        while j < chars.len() && chars[j].is_ascii_digit() {
+            j += 1;
+        }
+        if j == exp_start {
+            return Err(ParseJSONError::new(ErrorCode::InvalidNumber, j));
+        }
+        is_float = true;
+        i = j;
+    }
+    let text: String = chars[..i].iter().collect();
+    if is_float {
+        let value: f64 = text.parse().map_err(|_| ParseJSONError::new(ErrorCode::InvalidNumber, 0))?;
+        if !value.is_finite() {
+            return Err(ParseJSONError::new(ErrorCode::InvalidNumber, 0));
+        }
+        return Ok((JSONValue::Decimal(value), i));
+    }
+    match text.parse::<i64>() {
+        Ok(value) => Ok((JSONValue::Integer(value), i)),
+        Err(_) => {
+            let value: f64 = text.parse().map_err(|_| ParseJSONError::new(ErrorCode::InvalidNumber, 0))?;
+            if !value.is_finite() {
+                return Err(ParseJSONError::new(ErrorCode::InvalidNumber, 0));
             }
-            _ => {
-                return Ok((assemble_num(num, decimal_index, decimal, length, negative), length));
+            Ok((JSONValue::Decimal(value), i))
+        }
+    }
+}
+
+/// Iterates over a sequence of whitespace-separated JSON values, such as a
+/// multi-line pretty-printed document or an NDJSON log stream.
+///
+/// By default, a malformed value yields one `Err` and then the iterator
+/// resynchronizes at the next newline and keeps going, so one corrupt
+/// NDJSON record doesn't take down every record after it. There is no
+/// general way to resynchronize mid-value if the bad record contains no
+/// newline (a stray `{` could consume the rest of the input), in which
+/// case the iterator is exhausted. Call `halt_on_error()` to instead stop
+/// for good after the first error, e.g. for concatenated documents where
+/// a bad value likely means the rest of the stream is untrustworthy too.
+pub struct JSONValues {
+
+    chars: Vec<char>,
+    position: usize,
+    halt_on_error: bool,
+
+}
+
+impl JSONValues {
+
+    pub fn new(input: &str) -> JSONValues {
+        JSONValues {chars: input.chars().collect(), position: 0, halt_on_error: false}
+    }
+
+    pub fn from_reader<R: Read>(reader: &mut R) -> std::io::Result<JSONValues> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        Ok(JSONValues::new(&input))
+    }
+
+    /// Stops the iterator for good after the first malformed value instead
+    /// of resynchronizing at the next newline.
+    pub fn halt_on_error(mut self) -> JSONValues {
+        self.halt_on_error = true;
+        self
+    }
+
+}
+
+impl Iterator for JSONValues {
+    type Item = Result<JSONValue, ParseJSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (rest, trimmed) = trim_start(&self.chars[self.position..]);
+        self.position += trimmed;
+        if rest.is_empty() {
+            return None;
+        }
+        let start = self.position;
+        match parse_json(&self.chars[start..]) {
+            Ok((value, consumed)) => {
+                self.position += consumed;
+                Some(Ok(value))
+            }
+            Err(e) => {
+                if self.halt_on_error {
+                    self.position = self.chars.len();
+                } else {
+                    match self.chars[start..].iter().position(|&c| c == '\n') {
+                        Some(offset) => self.position = start + offset + 1,
+                        None => self.position = self.chars.len(),
+                    }
+                }
+                Some(Err(e.offset_by(start).locate(&self.chars)))
             }
         }
-        length += 1;
     }
-    Ok((assemble_num(num, decimal_index, decimal, length, negative), length))
+
 }
 
-fn assemble_num(num: i64, decimal_index: i32, decimal: i64, length: usize, negative: bool) -> JSONValue {
-    let mut num = num;
-    if negative {
-        num = -num;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_unicode_escape() {
+        let value: JSONValue = "\"\\u00e9\"".parse().unwrap();
+        assert_eq!(value.as_str(), Some("é"));
+    }
+
+    #[test]
+    fn decodes_surrogate_pair() {
+        let value: JSONValue = "\"\\ud83d\\ude00\"".parse().unwrap();
+        assert_eq!(value.as_str(), Some("😀"));
+    }
+
+    #[test]
+    fn decodes_backspace_formfeed_and_solidus_escapes() {
+        let value: JSONValue = "\"\\b\\f\\/\"".parse().unwrap();
+        assert_eq!(value.as_str(), Some("\u{0008}\u{000C}/"));
+    }
+
+    #[test]
+    fn rejects_lone_high_surrogate() {
+        let result = "\"\\ud83d\"".parse::<JSONValue>();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn rejects_leading_zero() {
+        assert!("01".parse::<JSONValue>().is_err());
+        assert!("0".parse::<JSONValue>().is_ok());
+        assert!("0.5".parse::<JSONValue>().is_ok());
+    }
+
+    #[test]
+    fn rejects_exponent_overflow() {
+        let result = "1e400".parse::<JSONValue>();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parses_negative_and_exponent_numbers() {
+        assert!(matches!("-5".parse::<JSONValue>(), Ok(JSONValue::Integer(-5))));
+        match "2.5e-3".parse::<JSONValue>() {
+            Ok(JSONValue::Decimal(n)) => assert!((n - 0.0025).abs() < 1e-12),
+            other => panic!("expected Decimal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_bad_map_key_at_its_own_position() {
+        let err = "{ 1:2}".parse::<JSONValue>().unwrap_err();
+        assert_eq!(err.code(), ErrorCode::KeyMustBeString);
+        assert_eq!(err.offset(), 2);
+        assert_eq!(err.column(), 3);
+    }
+
+    #[test]
+    fn display_round_trips_decimal_values() {
+        let original = JSONValue::Decimal(1.5);
+        let round_tripped: JSONValue = original.to_string().parse().unwrap();
+        assert!(matches!(round_tripped, JSONValue::Decimal(n) if (n - 1.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn rejects_trailing_characters() {
+        assert!("{\"a\":1} garbage".parse::<JSONValue>().is_err());
+        let (value, consumed) = parse_prefix("{\"a\":1} garbage").unwrap();
+        assert!(matches!(value, JSONValue::Map(_)));
+        assert_eq!(consumed, 7);
     }
-    if decimal_index == -1 {
-        return JSONValue::Integer(num);
+
+    #[test]
+    fn iterates_concatenated_pretty_printed_values() {
+        let input = "{\n  \"a\": 1\n}\n{\n  \"b\": 2\n}";
+        let values: Vec<JSONValue> = JSONValues::new(input).map(|v| v.unwrap()).collect();
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].get("a").and_then(|v| v.as_i64()), Some(1));
+        assert_eq!(values[1].get("b").and_then(|v| v.as_i64()), Some(2));
+    }
+
+    #[test]
+    fn iterates_ndjson_stream() {
+        let input = "{\"a\":1}\n{\"b\":2}\n{\"c\":3}\n";
+        let values: Vec<JSONValue> = JSONValues::new(input).map(|v| v.unwrap()).collect();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[2].get("c").and_then(|v| v.as_i64()), Some(3));
+    }
+
+    #[test]
+    fn ndjson_stream_resyncs_past_a_bad_line_by_default() {
+        let input = "{\"a\":1}\n{bad}\n{\"b\":2}\n";
+        let results: Vec<_> = JSONValues::new(input).collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[0].as_ref().unwrap().get("a").is_some());
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().get("b").and_then(|v| v.as_i64()), Some(2));
+    }
+
+    #[test]
+    fn halt_on_error_stops_after_first_bad_value() {
+        let input = "{\"a\":1}\n{bad}\n{\"b\":2}\n";
+        let results: Vec<_> = JSONValues::new(input).halt_on_error().collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[0].as_ref().unwrap().get("a").is_some());
+        assert!(results[1].is_err());
     }
-    let mut decimal = decimal as f64;
-    let length = length as f64;
-    let decimal_index = decimal_index as f64;
-    decimal = 10f64.powf(-length + decimal_index + 1f64) * decimal;
-    let mut num = num as f64 + decimal;
-    if negative {
-        num = -num;
+
+    #[test]
+    fn as_accessors_extract_matching_variants() {
+        assert_eq!(JSONValue::Integer(5).as_i64(), Some(5));
+        assert_eq!(JSONValue::Integer(5).as_f64(), Some(5.0));
+        assert_eq!(JSONValue::Decimal(1.5).as_f64(), Some(1.5));
+        assert_eq!(JSONValue::Boolean(true).as_bool(), Some(true));
+        assert_eq!(JSONValue::String("hi".to_string()).as_str(), Some("hi"));
+        assert!(JSONValue::List(vec![JSONValue::Null]).as_array().is_some());
+        assert!(JSONValue::Map(HashMap::new()).as_object().is_some());
+    }
+
+    #[test]
+    fn as_accessors_return_none_on_mismatch() {
+        assert_eq!(JSONValue::Null.as_i64(), None);
+        assert_eq!(JSONValue::Null.as_f64(), None);
+        assert_eq!(JSONValue::Null.as_bool(), None);
+        assert_eq!(JSONValue::Null.as_str(), None);
+        assert!(JSONValue::Null.as_array().is_none());
+        assert!(JSONValue::Null.as_object().is_none());
+    }
+
+    #[test]
+    fn get_and_get_index_support_nested_lookup() {
+        let value: JSONValue = "{\"user\":[{\"name\":\"ada\"}]}".parse().unwrap();
+        let name = value.get("user").and_then(|u| u.get_index(0)).and_then(|u| u.get("name"));
+        assert_eq!(name.and_then(|v| v.as_str()), Some("ada"));
+        assert!(value.get("missing").is_none());
+        assert!(value.get("user").and_then(|u| u.get_index(5)).is_none());
+    }
+
+    #[test]
+    fn try_from_converts_matching_variants() {
+        assert_eq!(i64::try_from(JSONValue::Integer(5)).unwrap(), 5);
+        assert_eq!(f64::try_from(JSONValue::Decimal(1.5)).unwrap(), 1.5);
+        assert_eq!(bool::try_from(JSONValue::Boolean(true)).unwrap(), true);
+        assert_eq!(String::try_from(JSONValue::String("hi".to_string())).unwrap(), "hi");
+        assert_eq!(Vec::<JSONValue>::try_from(JSONValue::List(vec![JSONValue::Null])).unwrap().len(), 1);
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), JSONValue::Null);
+        assert_eq!(HashMap::try_from(JSONValue::Map(map)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn try_from_reports_expected_and_found_on_mismatch() {
+        let err = i64::try_from(JSONValue::String("nope".to_string())).unwrap_err();
+        assert_eq!(err.expected, "integer");
+        assert_eq!(err.found, "string");
+
+        let err = bool::try_from(JSONValue::Null).unwrap_err();
+        assert_eq!(err.expected, "boolean");
+        assert_eq!(err.found, "null");
+
+        let err = String::try_from(JSONValue::Integer(1)).unwrap_err();
+        assert_eq!(err.expected, "string");
+        assert_eq!(err.found, "integer");
+
+        let err = Vec::<JSONValue>::try_from(JSONValue::Null).unwrap_err();
+        assert_eq!(err.expected, "list");
+        assert_eq!(err.found, "null");
+
+        let err = HashMap::<String, JSONValue>::try_from(JSONValue::Null).unwrap_err();
+        assert_eq!(err.expected, "map");
+        assert_eq!(err.found, "null");
     }
-    JSONValue::Decimal(num)
 }
@@ -1,13 +1,11 @@
 use jsonparser::*;
-use std::io::*;
 
 fn main() {
-    std::io::stdin().lock().lines().for_each(|l| {
-        let line = l.unwrap();
-        let parsed = line.parse::<JSONValue>();
+    let values = JSONValues::from_reader(&mut std::io::stdin()).unwrap();
+    for parsed in values {
         match parsed {
             Ok(v) => println!("{:?}", v),
             Err(e) => println!("{:?}", e)
         }
-    });
+    }
 }